@@ -126,6 +126,9 @@ const RESERVED: [&str; 116] = [
     "without",
 ];
 
+// SQLite caps the number of bound parameters per statement at this value.
+const SQLITE_MAX_VARIABLE_NUMBER: usize = 32766;
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
@@ -144,6 +147,8 @@ struct Args {
     /// Batch size.
     ///
     /// When debugging: Reduce to 1 to identify the row that is causing the error.
+    /// Capped automatically so `batch_size * headers.len()` stays under SQLite's
+    /// per-statement bound parameter limit.
     #[clap(short, long, default_value = "10000")]
     batch_size: usize,
 
@@ -156,6 +161,66 @@ struct Args {
     /// This is useful rows have mixed data types.
     #[clap(long, default_value = "false")]
     no_auto_detect_types: bool,
+
+    /// Number of rows to sample for type detection.
+    ///
+    /// By default the whole file is scanned so a column that starts as an
+    /// integer but later contains a float or text value is still detected
+    /// correctly. Set this to scan only the first N rows on very large files.
+    #[clap(long)]
+    sample_rows: Option<usize>,
+
+    /// Create a virtual table backed directly by the CSV file, instead of
+    /// copying rows into it.
+    ///
+    /// Uses SQLite's `csvtab` extension so table creation is instant
+    /// regardless of file size, and the CSV file stays the source of truth.
+    ///
+    /// Requires SQLite's `csv` loadable extension (built from sqlite's
+    /// ext/misc/csv.c into csv.so/.dylib/.dll) to already be on the
+    /// dynamic loader's search path -- this binary does not build, vendor,
+    /// or ship it. Without it, connecting to the database fails with an
+    /// extension-load error.
+    #[clap(long = "virtual", default_value = "false")]
+    r#virtual: bool,
+
+    /// Relax durability for the duration of the import (journal_mode=OFF,
+    /// synchronous=OFF, temp_store=MEMORY) to speed up bulk loads.
+    ///
+    /// The database is restored to journal_mode=WAL and synchronous=NORMAL
+    /// once the import transaction commits, so the resulting file is durable.
+    #[clap(long, default_value = "false")]
+    fast: bool,
+
+    /// Page size (bytes) to set when creating the database under --fast.
+    #[clap(long, default_value = "8192")]
+    page_size: u32,
+
+    /// Cache size (KiB) to set under --fast.
+    #[clap(long, default_value = "200000")]
+    cache_size: i64,
+
+    /// Memory-map size (bytes) to set under --fast.
+    #[clap(long, default_value = "268435456")]
+    mmap_size: i64,
+
+    /// Do not detect JSON columns.
+    ///
+    /// By default a column whose cells all parse as a JSON object or array
+    /// is stored as `TEXT` with a `json_valid()` CHECK constraint. Pass this
+    /// for files with malformed JSON-looking fields that should stay plain text.
+    #[clap(long, default_value = "false")]
+    no_json: bool,
+
+    /// Additional chrono strftime pattern to try when detecting date/datetime
+    /// columns, tried before the built-in patterns. May be repeated.
+    ///
+    /// Patterns with a time component (including the built-in
+    /// `%Y-%m-%d %H:%M:%S`) are parsed as naive, timezone-less timestamps
+    /// and stored as if they were UTC. If your file's timestamps are in
+    /// local time, the stored values will be mislabelled by that offset.
+    #[clap(long)]
+    date_formats: Vec<String>,
 }
 
 #[tokio::main]
@@ -168,10 +233,10 @@ async fn main() {
         .table
         .clone()
         .unwrap_or_else(|| args.file.replace(".csv", ""));
-    let f = std::fs::File::open(args.file).expect("read csv file");
-    let mut csv_r = csv::Reader::from_reader(f);
 
     println!("Parsing headers...");
+    let f = std::fs::File::open(&args.file).expect("read csv file");
+    let mut csv_r = csv::Reader::from_reader(f);
     let headers = csv_r
         .headers()
         .expect("headers")
@@ -179,21 +244,66 @@ async fn main() {
         .map(|h| h.to_string())
         .collect::<Vec<_>>();
 
-    println!("Determining data type based on the first row...");
-    let first_row = csv_r
-        .records()
-        .next()
-        .expect("first row")
-        .expect("first row");
-    let headers = first_row
-        .iter()
-        .zip(headers.iter())
-        .map(|(val, header)| CsvHeader::new(header.to_string(), determine_sql_type(val)))
-        .map(|header| match args.no_auto_detect_types {
-            true => header.with_type(SqlType::String),
-            false => header,
-        })
-        .collect::<Vec<_>>();
+    let headers = if args.r#virtual {
+        println!("Virtual mode enabled, skipping type detection...");
+        headers
+            .into_iter()
+            .map(|header| CsvHeader::new(header, SqlType::String))
+            .collect::<Vec<_>>()
+    } else {
+        println!(
+            "Determining data types ({})...",
+            match args.sample_rows {
+                Some(n) => format!("sampling first {n} rows"),
+                None => "scanning whole file".to_string(),
+            }
+        );
+        let date_fmts = date_formats(&args.date_formats);
+        let coarse_types = infer_column_types(
+            &mut csv_r,
+            headers.len(),
+            args.sample_rows,
+            !args.no_json,
+            &date_fmts,
+        );
+
+        // A column only keeps its NaiveDate/IsoDateTime type if a single
+        // format parses every row; reopen the file for that targeted pass.
+        let f = std::fs::File::open(&args.file).expect("read csv file");
+        let mut date_csv_r = csv::Reader::from_reader(f);
+        let resolved_formats = resolve_date_column_formats(
+            &mut date_csv_r,
+            &coarse_types,
+            args.sample_rows,
+            &date_fmts,
+        );
+
+        let types = coarse_types
+            .iter()
+            .zip(&resolved_formats)
+            .map(|(ty, fmt)| match (*ty, fmt) {
+                (SqlType::NaiveDate | SqlType::IsoDateTime, None) => SqlType::String,
+                (ty, _) => ty,
+            })
+            .collect::<Vec<_>>();
+
+        headers
+            .into_iter()
+            .zip(types)
+            .zip(resolved_formats)
+            .map(|((header, ty), fmt)| {
+                let header = CsvHeader::new(header, ty);
+                match fmt {
+                    Some(fmt) => header.with_date_format(fmt),
+                    None => header,
+                }
+            })
+            .map(|header| match args.no_auto_detect_types {
+                true => header.with_type(SqlType::String),
+                false => header,
+            })
+            .collect::<Vec<_>>()
+    };
 
     headers.iter().for_each(|header| {
         println!(
@@ -202,7 +312,11 @@ async fn main() {
         );
     });
 
-    let create_table_sql = create_table_sql(&table_name, &headers);
+    let create_table_sql = if args.r#virtual {
+        create_virtual_table_sql(&table_name, &headers, &args.file)
+    } else {
+        create_table_sql(&table_name, &headers)
+    };
 
     if args.dry_run {
         println!("Creating database: {}", args.db);
@@ -220,38 +334,84 @@ async fn main() {
         .filename(args.db)
         .create_if_missing(true)
         .statement_cache_capacity(0); // nothing to cache - this reduces memory leaks
+    let opt = if args.r#virtual {
+        // Enable extension loading and load SQLite's CSV virtual-table module.
+        opt.extension("csv")
+    } else {
+        opt
+    };
     let pool = SqlitePoolOptions::new()
         .max_connections(1)
         .connect_with(opt)
         .await
         .expect("connect to db");
 
+    // Virtual tables have no insert loop to speed up, so the --fast profile
+    // (and its matching restore once the import commits) doesn't apply there.
+    if args.fast && !args.r#virtual {
+        println!(
+            "Applying --fast PRAGMA profile (page_size={}, cache_size={}KiB, mmap_size={})...",
+            args.page_size, args.cache_size, args.mmap_size
+        );
+        apply_fast_pragmas(&pool, &args)
+            .await
+            .expect("apply fast pragmas");
+    }
+
     println!("Creating table:");
     println!("{}", create_table_sql);
 
-    sqlx::raw_sql(&create_table_sql)
-        .execute(&pool)
-        .await
-        .expect("create table");
+    if let Err(e) = sqlx::raw_sql(&create_table_sql).execute(&pool).await {
+        if args.r#virtual {
+            panic!(
+                "{e:#?}\n--virtual requires SQLite's `csv` loadable extension (built from \
+                 sqlite's ext/misc/csv.c into csv.so/.dylib/.dll) to be on the dynamic \
+                 loader's search path (e.g. LD_LIBRARY_PATH on Linux) -- this binary does not \
+                 build or vendor it. Compile and install the extension, or drop --virtual to \
+                 copy rows into a real table instead."
+            );
+        } else {
+            panic!("{e:#?}\nfailed to create table");
+        }
+    }
+
+    if args.r#virtual {
+        println!("Virtual table created, file stays the source of truth. Exiting...");
+        return;
+    }
 
     println!("Inserting rows...");
     let mut inserted = 0;
     let mut inserted_bytes = 0;
 
-    let remaining = csv_r.records().flat_map(|r| r.ok());
+    // Keep the number of bound parameters per statement under SQLite's limit.
+    let effective_batch_size = args
+        .batch_size
+        .min(SQLITE_MAX_VARIABLE_NUMBER / headers.len().max(1));
+
+    let f = std::fs::File::open(&args.file).expect("read csv file");
+    let mut csv_r = csv::Reader::from_reader(f);
+    let rows = csv_r.records().flat_map(|r| r.ok());
     let started = Instant::now();
-    let mut sql_buffer = String::with_capacity(5 * args.batch_size);
+    let mut sql_buffer = String::with_capacity(5 * effective_batch_size);
     let mut tnx = pool.begin().await.expect("begin transaction");
-    for batch in &vec![first_row]
-        .into_iter()
-        .chain(remaining)
-        .chunks(args.batch_size)
-    {
+    for batch in &rows.chunks(effective_batch_size) {
         let rows = batch.collect::<Vec<_>>();
 
-        let added = insert_row_sql_batch(&mut sql_buffer, &table_name, &headers, rows);
+        let (added, values) = insert_row_sql_batch(&mut sql_buffer, &table_name, &headers, &rows);
 
-        match sqlx::query(&sql_buffer).execute(&mut *tnx).await {
+        let mut query = sqlx::query(&sql_buffer);
+        for v in &values {
+            query = match v {
+                BoundValue::Null => query.bind(None::<String>),
+                BoundValue::Integer(i) => query.bind(*i),
+                BoundValue::Float(f) => query.bind(*f),
+                BoundValue::Boolean(b) => query.bind(*b),
+                BoundValue::Text(s) => query.bind(s.clone()),
+            };
+        }
+
+        match query.execute(&mut *tnx).await {
             Ok(_) => {}
             Err(e) => {
                 // write to a file
@@ -284,12 +444,27 @@ async fn main() {
     print!("\rFinalising...");
     tnx.commit().await.expect("commit transaction");
 
+    if args.fast {
+        restore_durable_pragmas(&pool)
+            .await
+            .expect("restore durable pragmas");
+    }
+
     let rps = inserted as f32 / started.elapsed().as_secs_f32();
+    let fast_summary = if args.fast {
+        format!(
+            " [fast: page_size={}, cache_size={}KiB, mmap_size={}]",
+            args.page_size, args.cache_size, args.mmap_size
+        )
+    } else {
+        String::new()
+    };
     println!(
-        "Done! Inserted {} rows ({} bytes) at ({:.2} rps)",
+        "Done! Inserted {} rows ({} bytes) at ({:.2} rps){}",
         inserted.to_formatted_string(&Locale::en),
         inserted_bytes.to_formatted_string(&Locale::en),
-        rps
+        rps,
+        fast_summary
     );
 }
 
@@ -297,9 +472,10 @@ fn insert_row_sql_batch(
     buffer: &mut String,
     table: &str,
     headers: &[CsvHeader],
-    rows: Vec<StringRecord>,
-) -> usize {
+    rows: &[StringRecord],
+) -> (usize, Vec<BoundValue>) {
     let mut count = 0;
+    let mut values = Vec::with_capacity(rows.len() * headers.len());
     buffer.clear();
 
     buffer.push_str("INSERT INTO ");
@@ -312,30 +488,102 @@ fn insert_row_sql_batch(
     buffer.remove(buffer.len() - 2);
     buffer.push_str(") VALUES ");
 
+    let placeholders = format!("({})", vec!["?"; headers.len()].join(", "));
+
     for row in rows {
-        buffer.push('(');
-
-        headers.iter().zip(row.iter()).for_each(|(h, v)| {
-            if v.is_empty() {
-                buffer.push_str("NULL, ");
-            } else if h.need_quotes() {
-                buffer.push_str(&format!(
-                    "'{}', ",
-                    v.replace(r#"""#, r#"\""#).replace("'", "''")
-                ));
-            } else {
-                buffer.push_str(&format!("{}, ", v));
-            }
-        });
+        buffer.push_str(&placeholders);
+        buffer.push_str(", ");
+
+        headers
+            .iter()
+            .zip(row.iter())
+            .for_each(|(h, v)| values.push(BoundValue::from_cell(v, h)));
 
-        buffer.remove(buffer.len() - 2);
-        buffer.push_str("), ");
         count += 1;
     }
 
     buffer.remove(buffer.len() - 2);
 
-    count
+    (count, values)
+}
+
+/// A single cell, typed and ready to be bound to a `?` placeholder.
+#[derive(Debug, Clone)]
+enum BoundValue {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Text(String),
+}
+
+impl BoundValue {
+    fn from_cell(v: &str, header: &CsvHeader) -> Self {
+        if v.is_empty() {
+            return BoundValue::Null;
+        }
+
+        match header.ty {
+            SqlType::Integer => BoundValue::Integer(v.parse().expect("valid integer cell")),
+            SqlType::Float => BoundValue::Float(v.parse().expect("valid float cell")),
+            SqlType::Boolean => BoundValue::Boolean(v.eq_ignore_ascii_case("true")),
+            SqlType::NaiveDate | SqlType::IsoDateTime => {
+                let format = header
+                    .date_format
+                    .as_ref()
+                    .expect("date/datetime column missing a resolved format");
+                let iso = parse_date_format(v, format)
+                    .expect("cell matching the format detected for this column");
+                BoundValue::Text(iso)
+            }
+            SqlType::String | SqlType::Json => BoundValue::Text(v.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod insert_row_sql_batch_tests {
+    use super::*;
+
+    #[test]
+    fn quotes_backslashes_and_newlines_are_bound_not_interpolated() {
+        let headers = vec![CsvHeader::new("name".to_string(), SqlType::String)];
+        let tricky = "O'Brien \"quoted\" C:\\path\nsecond line -- DROP TABLE t;--";
+        let rows = vec![StringRecord::from(vec![tricky])];
+
+        let mut buffer = String::new();
+        let (count, values) = insert_row_sql_batch(&mut buffer, "t", &headers, &rows);
+
+        assert_eq!(count, 1);
+        assert!(
+            !buffer.contains(tricky),
+            "cell content must not be interpolated into the SQL text: {buffer}"
+        );
+        assert!(buffer.starts_with("INSERT INTO t ("));
+        assert!(buffer.contains("VALUES"));
+        assert_eq!(
+            buffer.matches('?').count(),
+            1,
+            "exactly one placeholder: {buffer}"
+        );
+
+        assert_eq!(values.len(), 1);
+        match &values[0] {
+            BoundValue::Text(s) => assert_eq!(s, tricky),
+            other => panic!("expected BoundValue::Text, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn empty_cell_binds_null() {
+        let headers = vec![CsvHeader::new("n".to_string(), SqlType::Integer)];
+        let rows = vec![StringRecord::from(vec![""])];
+
+        let mut buffer = String::new();
+        let (_, values) = insert_row_sql_batch(&mut buffer, "t", &headers, &rows);
+
+        assert!(matches!(values[0], BoundValue::Null));
+    }
 }
 
 fn create_table_sql(name: &str, items: &[CsvHeader]) -> String {
@@ -343,6 +591,12 @@ fn create_table_sql(name: &str, items: &[CsvHeader]) -> String {
     for h in items {
         sql.push_str(&format!("\n  {} ", h.normalised));
         sql.push_str(h.ty_str());
+        if matches!(h.ty, SqlType::Json) {
+            sql.push_str(&format!(
+                " CHECK({0} IS NULL OR json_valid({0}))",
+                h.normalised
+            ));
+        }
         sql.push(',');
     }
     sql.remove(sql.len() - 1);
@@ -350,11 +604,84 @@ fn create_table_sql(name: &str, items: &[CsvHeader]) -> String {
     sql
 }
 
+/// Build a `CREATE VIRTUAL TABLE ... USING csv(...)` statement that reads
+/// `file` directly via SQLite's `csvtab` extension, so no rows are copied.
+/// An explicit `schema` is passed so the (normalised) column names match
+/// what a regular import would produce.
+fn create_virtual_table_sql(name: &str, items: &[CsvHeader], file: &str) -> String {
+    let schema = items
+        .iter()
+        .map(|h| format!("{} TEXT", h.normalised))
+        .join(", ");
+    let file = file.replace('\'', "''");
+    format!(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS {name} USING csv(filename='{file}', header=YES, schema='CREATE TABLE x({schema})');"
+    )
+}
+
+#[cfg(test)]
+mod create_virtual_table_sql_tests {
+    use super::*;
+
+    #[test]
+    fn single_quotes_in_filename_are_escaped() {
+        let items = vec![CsvHeader::new("id".to_string(), SqlType::String)];
+        let sql = create_virtual_table_sql("t", &items, "O'Brien's data.csv");
+
+        assert!(
+            sql.contains("O''Brien''s data.csv"),
+            "embedded quotes must be doubled, not left as-is: {sql}"
+        );
+        assert!(
+            !sql.contains("filename='O'Brien"),
+            "an unescaped quote would terminate the string literal early: {sql}"
+        );
+    }
+}
+
+/// Relax durability for the duration of the import so the insert loop isn't
+/// bottlenecked on fsync/journalling. Pairs with `restore_durable_pragmas`,
+/// which is called once the import transaction has committed.
+async fn apply_fast_pragmas(pool: &sqlx::SqlitePool, args: &Args) -> Result<(), sqlx::Error> {
+    sqlx::raw_sql(&format!("PRAGMA page_size={};", args.page_size))
+        .execute(pool)
+        .await?;
+    sqlx::raw_sql("PRAGMA journal_mode=OFF;")
+        .execute(pool)
+        .await?;
+    sqlx::raw_sql("PRAGMA synchronous=OFF;")
+        .execute(pool)
+        .await?;
+    sqlx::raw_sql("PRAGMA temp_store=MEMORY;")
+        .execute(pool)
+        .await?;
+    sqlx::raw_sql(&format!("PRAGMA cache_size=-{};", args.cache_size))
+        .execute(pool)
+        .await?;
+    sqlx::raw_sql(&format!("PRAGMA mmap_size={};", args.mmap_size))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Restore the durability settings relaxed by `apply_fast_pragmas` so the
+/// database left behind by `--fast` is safe for normal use.
+async fn restore_durable_pragmas(pool: &sqlx::SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::raw_sql("PRAGMA journal_mode=WAL;")
+        .execute(pool)
+        .await?;
+    sqlx::raw_sql("PRAGMA synchronous=NORMAL;")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 struct CsvHeader {
     title: String,
     normalised: String,
     ty: SqlType,
+    date_format: Option<DateFormat>,
 }
 
 impl CsvHeader {
@@ -366,28 +693,30 @@ impl CsvHeader {
             SqlType::IsoDateTime => "TIMESTAMP",
             SqlType::NaiveDate => "DATE",
             SqlType::Boolean => "BOOLEAN",
+            SqlType::Json => "TEXT",
         }
     }
 
-    pub fn need_quotes(&self) -> bool {
-        match self.ty {
-            SqlType::String => true,
-            SqlType::IsoDateTime => true,
-            SqlType::NaiveDate => true,
-            SqlType::Integer | SqlType::Boolean | SqlType::Float => false,
+    pub fn with_type(&self, ty: SqlType) -> Self {
+        Self {
+            title: self.title.clone(),
+            normalised: self.normalised.clone(),
+            ty,
+            date_format: None,
         }
     }
 
-    pub fn with_type(&self, ty: SqlType) -> Self {
+    pub fn with_date_format(&self, date_format: DateFormat) -> Self {
         Self {
             title: self.title.clone(),
             normalised: self.normalised.clone(),
-            ty,
+            ty: self.ty,
+            date_format: Some(date_format),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum SqlType {
     String,
     Integer,
@@ -395,6 +724,7 @@ enum SqlType {
     Float,
     IsoDateTime,
     NaiveDate,
+    Json,
 }
 
 impl CsvHeader {
@@ -418,22 +748,399 @@ impl CsvHeader {
             title,
             normalised,
             ty,
+            date_format: None,
         }
     }
 }
 
-fn determine_sql_type(val: &str) -> SqlType {
+/// A datetime/date format a column's cells may be parsed with, tried in
+/// priority order until one matches. `Pattern` wraps a chrono strftime
+/// pattern (user-supplied via `--date-formats`, or one of the built-ins);
+/// its `kind` says whether it parses a bare date or a full datetime.
+#[derive(Debug, Clone, PartialEq)]
+enum DateFormat {
+    Rfc3339,
+    EpochSeconds,
+    Pattern { fmt: String, kind: DateFormatKind },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateFormatKind {
+    Date,
+    DateTime,
+}
+
+/// Built-in chrono patterns tried (in this order) after RFC 3339 and before
+/// Unix epoch seconds.
+const BUILTIN_DATE_PATTERNS: [(&str, DateFormatKind); 4] = [
+    ("%Y-%m-%d", DateFormatKind::Date),
+    ("%d/%m/%Y", DateFormatKind::Date),
+    ("%m/%d/%Y", DateFormatKind::Date),
+    ("%Y-%m-%d %H:%M:%S", DateFormatKind::DateTime),
+];
+
+/// Unix epoch seconds are only accepted in this range (roughly 2001-09-09
+/// to 2100-01-01), so that an ordinary integer column isn't misread as dates.
+const EPOCH_SECONDS_RANGE: std::ops::RangeInclusive<i64> = 1_000_000_000..=4_102_444_800;
+
+/// Build the ordered list of date/datetime formats to try: user-supplied
+/// `--date-formats` patterns first, then RFC 3339, then the built-ins, then
+/// Unix epoch seconds last (it is the most prone to false positives).
+fn date_formats(user_formats: &[String]) -> Vec<DateFormat> {
+    let mut formats = user_formats
+        .iter()
+        .map(|fmt| DateFormat::Pattern {
+            fmt: fmt.clone(),
+            kind: date_format_kind(fmt),
+        })
+        .collect::<Vec<_>>();
+
+    formats.push(DateFormat::Rfc3339);
+    formats.extend(
+        BUILTIN_DATE_PATTERNS
+            .iter()
+            .map(|(fmt, kind)| DateFormat::Pattern {
+                fmt: fmt.to_string(),
+                kind: *kind,
+            }),
+    );
+    formats.push(DateFormat::EpochSeconds);
+
+    formats
+}
+
+/// A pattern containing a time specifier parses a full datetime; otherwise
+/// it's treated as a bare date.
+fn date_format_kind(fmt: &str) -> DateFormatKind {
+    const TIME_SPECIFIERS: [&str; 6] = ["%H", "%M", "%S", "%T", "%X", "%I"];
+    if TIME_SPECIFIERS.iter().any(|spec| fmt.contains(spec)) {
+        DateFormatKind::DateTime
+    } else {
+        DateFormatKind::Date
+    }
+}
+
+/// Try to parse `val` with `format`, returning the canonical ISO-8601
+/// representation (date or RFC 3339 datetime) on success.
+/// Parse `val` against `format`, returning the canonical ISO-8601 string to
+/// store (a plain date, or an RFC3339 timestamp).
+///
+/// Patterns of `DateFormatKind::DateTime` (including the built-in
+/// `%Y-%m-%d %H:%M:%S`) parse to a naive, timezone-less `NaiveDateTime` and
+/// are stamped `+00:00` as if they were UTC. If the source file's
+/// timestamps are actually in local time, this mislabels them, and
+/// downstream `datetime()` comparisons against real UTC values will be off
+/// by the local offset -- there is currently no way to specify a source
+/// timezone for a `--date-formats` pattern.
+fn parse_date_format(val: &str, format: &DateFormat) -> Option<String> {
+    match format {
+        DateFormat::Rfc3339 => chrono::DateTime::parse_from_rfc3339(val)
+            .ok()
+            .map(|dt| dt.to_rfc3339()),
+        DateFormat::EpochSeconds => val
+            .parse::<i64>()
+            .ok()
+            .filter(|secs| EPOCH_SECONDS_RANGE.contains(secs))
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|dt| dt.to_rfc3339()),
+        DateFormat::Pattern {
+            fmt,
+            kind: DateFormatKind::Date,
+        } => chrono::NaiveDate::parse_from_str(val, fmt)
+            .ok()
+            .map(|d| d.to_string()),
+        DateFormat::Pattern {
+            fmt,
+            kind: DateFormatKind::DateTime,
+        } => chrono::NaiveDateTime::parse_from_str(val, fmt)
+            .ok()
+            .map(|dt| dt.and_utc().to_rfc3339()),
+    }
+}
+
+fn date_format_sql_type(format: &DateFormat) -> SqlType {
+    match format {
+        DateFormat::Rfc3339 | DateFormat::EpochSeconds => SqlType::IsoDateTime,
+        DateFormat::Pattern {
+            kind: DateFormatKind::Date,
+            ..
+        } => SqlType::NaiveDate,
+        DateFormat::Pattern {
+            kind: DateFormatKind::DateTime,
+            ..
+        } => SqlType::IsoDateTime,
+    }
+}
+
+fn determine_sql_type(val: &str, detect_json: bool, date_formats: &[DateFormat]) -> SqlType {
+    // Epoch seconds are indistinguishable from an ordinary big integer, so
+    // they are checked last — otherwise a plain integer column (order IDs,
+    // counts, ...) that happens to fall in the epoch range would be silently
+    // rewritten as a datetime. Every value in `EPOCH_SECONDS_RANGE` also
+    // parses as an `i64` and is classified `Integer` first, so a column of
+    // epoch timestamps is typed `Integer`, never `IsoDateTime`.
+    if let Some(format) = date_formats
+        .iter()
+        .filter(|format| !matches!(format, DateFormat::EpochSeconds))
+        .find(|format| parse_date_format(val, format).is_some())
+    {
+        return date_format_sql_type(format);
+    }
+
     if val.parse::<i64>().is_ok() {
-        SqlType::Integer
-    } else if val.parse::<f64>().is_ok() {
-        SqlType::Float
-    } else if chrono::DateTime::parse_from_rfc3339(val).is_ok() {
-        SqlType::IsoDateTime
-    } else if chrono::NaiveDate::parse_from_str(val, "%Y-%m-%d").is_ok() {
-        SqlType::NaiveDate
-    } else if val.eq_ignore_ascii_case("true") || val.eq_ignore_ascii_case("false") {
-        SqlType::Boolean
+        return SqlType::Integer;
+    }
+
+    if val.parse::<f64>().is_ok() {
+        return SqlType::Float;
+    }
+
+    if val.eq_ignore_ascii_case("true") || val.eq_ignore_ascii_case("false") {
+        return SqlType::Boolean;
+    }
+
+    if detect_json && is_json_object_or_array(val) {
+        return SqlType::Json;
+    }
+
+    if let Some(format) = date_formats.iter().find(|format| {
+        matches!(format, DateFormat::EpochSeconds) && parse_date_format(val, format).is_some()
+    }) {
+        return date_format_sql_type(format);
+    }
+
+    SqlType::String
+}
+
+/// `true` if `val` parses as JSON whose root is an object or array. Bare
+/// scalars (numbers, strings, booleans, null) are excluded since those are
+/// already covered by the other `determine_sql_type` checks.
+fn is_json_object_or_array(val: &str) -> bool {
+    matches!(
+        serde_json::from_str::<serde_json::Value>(val),
+        Ok(serde_json::Value::Object(_)) | Ok(serde_json::Value::Array(_))
+    )
+}
+
+#[cfg(test)]
+mod json_detection_tests {
+    use super::*;
+
+    #[test]
+    fn objects_and_arrays_are_detected() {
+        assert!(is_json_object_or_array(r#"{"a": 1}"#));
+        assert!(is_json_object_or_array(r#"[1, 2, 3]"#));
+    }
+
+    #[test]
+    fn bare_scalars_are_not_detected() {
+        assert!(!is_json_object_or_array("42"));
+        assert!(!is_json_object_or_array("true"));
+        assert!(!is_json_object_or_array(r#""just a string""#));
+        assert!(!is_json_object_or_array("not json at all"));
+    }
+}
+
+/// Infer the `SqlType` of each column by scanning rows (up to `sample_rows`,
+/// or the whole file when `None`) and widening per-cell types as conflicts
+/// are found. Empty cells are ignored; a column made up only of empty cells
+/// falls back to `String`.
+fn infer_column_types<R: std::io::Read>(
+    csv_r: &mut csv::Reader<R>,
+    n_cols: usize,
+    sample_rows: Option<usize>,
+    detect_json: bool,
+    date_formats: &[DateFormat],
+) -> Vec<SqlType> {
+    let mut widened: Vec<Option<SqlType>> = vec![None; n_cols];
+
+    let records = csv_r.records().flat_map(|r| r.ok());
+    let records: Box<dyn Iterator<Item = StringRecord>> = match sample_rows {
+        Some(n) => Box::new(records.take(n)),
+        None => Box::new(records),
+    };
+
+    for row in records {
+        for (slot, val) in widened.iter_mut().zip(row.iter()) {
+            if val.is_empty() {
+                continue;
+            }
+
+            let observed = determine_sql_type(val, detect_json, date_formats);
+            *slot = Some(match slot {
+                Some(current) => widen_sql_type(*current, observed),
+                None => observed,
+            });
+        }
+    }
+
+    widened
+        .into_iter()
+        .map(|ty| ty.unwrap_or(SqlType::String))
+        .collect()
+}
+
+/// Widen two observed column types into one both are compatible with.
+///
+/// `Integer ⊂ Float ⊂ String`; `Boolean`/`NaiveDate`/`IsoDateTime` only
+/// survive if every cell agrees, otherwise the column falls straight to
+/// `String`.
+fn widen_sql_type(a: SqlType, b: SqlType) -> SqlType {
+    match (a, b) {
+        (x, y) if x == y => x,
+        (SqlType::Integer, SqlType::Float) | (SqlType::Float, SqlType::Integer) => SqlType::Float,
+        _ => SqlType::String,
+    }
+}
+
+#[cfg(test)]
+mod widen_sql_type_tests {
+    use super::*;
+
+    #[test]
+    fn same_type_is_identity() {
+        assert_eq!(
+            widen_sql_type(SqlType::Integer, SqlType::Integer),
+            SqlType::Integer
+        );
+        assert_eq!(
+            widen_sql_type(SqlType::Boolean, SqlType::Boolean),
+            SqlType::Boolean
+        );
+    }
+
+    #[test]
+    fn integer_and_float_widen_to_float() {
+        assert_eq!(
+            widen_sql_type(SqlType::Integer, SqlType::Float),
+            SqlType::Float
+        );
+        assert_eq!(
+            widen_sql_type(SqlType::Float, SqlType::Integer),
+            SqlType::Float
+        );
+    }
+
+    #[test]
+    fn mismatched_non_numeric_types_fall_back_to_string() {
+        assert_eq!(
+            widen_sql_type(SqlType::Boolean, SqlType::Integer),
+            SqlType::String
+        );
+        assert_eq!(
+            widen_sql_type(SqlType::NaiveDate, SqlType::IsoDateTime),
+            SqlType::String
+        );
+    }
+}
+
+/// For every column whose coarse type is `NaiveDate`/`IsoDateTime`, find the
+/// single `DateFormat` that parses every non-empty cell. A column where
+/// different rows only agree via different formats has no consistent
+/// pattern and resolves to `None`, so the caller can fall it back to
+/// `String` instead of silently picking an arbitrary format.
+fn resolve_date_column_formats<R: std::io::Read>(
+    csv_r: &mut csv::Reader<R>,
+    coarse_types: &[SqlType],
+    sample_rows: Option<usize>,
+    date_formats: &[DateFormat],
+) -> Vec<Option<DateFormat>> {
+    let mut candidates: Vec<Option<Vec<DateFormat>>> = coarse_types
+        .iter()
+        .map(|ty| match ty {
+            SqlType::NaiveDate | SqlType::IsoDateTime => Some(date_formats.to_vec()),
+            _ => None,
+        })
+        .collect();
+
+    if candidates.iter().all(Option::is_none) {
+        return vec![None; coarse_types.len()];
+    }
+
+    let records = csv_r.records().flat_map(|r| r.ok());
+    let records: Box<dyn Iterator<Item = StringRecord>> = match sample_rows {
+        Some(n) => Box::new(records.take(n)),
+        None => Box::new(records),
+    };
+
+    for row in records {
+        for (slot, val) in candidates.iter_mut().zip(row.iter()) {
+            if val.is_empty() {
+                continue;
+            }
+
+            if let Some(remaining) = slot {
+                remaining.retain(|format| parse_date_format(val, format).is_some());
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .map(|c| c.and_then(resolve_unambiguous_format))
+        .collect()
+}
+
+/// Picks the single surviving format, or `None` if zero or more than one
+/// candidate survived — e.g. a column of dates where day and month are both
+/// `<=12` matches both `%d/%m/%Y` and `%m/%d/%Y`, and picking either one
+/// arbitrarily would silently transpose day/month for half the files that
+/// use the other convention.
+fn resolve_unambiguous_format(mut formats: Vec<DateFormat>) -> Option<DateFormat> {
+    if formats.len() == 1 {
+        formats.pop()
     } else {
-        SqlType::String
+        None
+    }
+}
+
+#[cfg(test)]
+mod date_format_tests {
+    use super::*;
+
+    fn reader_from(csv_data: &str) -> csv::Reader<std::io::Cursor<Vec<u8>>> {
+        csv::Reader::from_reader(std::io::Cursor::new(csv_data.as_bytes().to_vec()))
+    }
+
+    #[test]
+    fn determine_sql_type_does_not_reclassify_large_integers_as_epoch_seconds() {
+        let formats = date_formats(&[]);
+        assert_eq!(
+            determine_sql_type("1700000000", true, &formats),
+            SqlType::Integer
+        );
+    }
+
+    #[test]
+    fn determine_sql_type_still_detects_genuine_dates() {
+        let formats = date_formats(&[]);
+        assert_eq!(
+            determine_sql_type("2024-01-15", true, &formats),
+            SqlType::NaiveDate
+        );
+    }
+
+    #[test]
+    fn resolve_date_column_formats_treats_ambiguous_day_month_as_unresolved() {
+        let mut r = reader_from("d\n01/02/2020\n03/04/2020\n05/06/2020\n");
+        let coarse = vec![SqlType::NaiveDate];
+        let formats = date_formats(&[]);
+        let resolved = resolve_date_column_formats(&mut r, &coarse, None, &formats);
+        assert_eq!(resolved, vec![None]);
+    }
+
+    #[test]
+    fn resolve_date_column_formats_resolves_unambiguous_pattern() {
+        let mut r = reader_from("d\n25/12/2020\n31/01/2021\n");
+        let coarse = vec![SqlType::NaiveDate];
+        let formats = date_formats(&[]);
+        let resolved = resolve_date_column_formats(&mut r, &coarse, None, &formats);
+        assert_eq!(
+            resolved,
+            vec![Some(DateFormat::Pattern {
+                fmt: "%d/%m/%Y".to_string(),
+                kind: DateFormatKind::Date
+            })]
+        );
     }
 }